@@ -1,6 +1,17 @@
 use std::path::PathBuf;
 use std::fs;
 
+/// Returns the Rust target triple the sidecar must be built for.
+///
+/// Cargo always sets `TARGET` for build scripts; Tauri's CLI additionally
+/// exposes `TAURI_TARGET_TRIPLE` to build hooks when cross-compiling, so
+/// prefer that one when it's present.
+fn target_triple() -> String {
+    std::env::var("TAURI_TARGET_TRIPLE")
+        .or_else(|_| std::env::var("TARGET"))
+        .expect("neither TAURI_TARGET_TRIPLE nor TARGET is set")
+}
+
 fn main() {
     tauri_build::build();
 
@@ -9,28 +20,35 @@ fn main() {
     let profile = std::env::var("PROFILE").unwrap_or_else(|_| "debug".to_string());
     let output_dir = target_dir.join(&profile);
     let source_dir = manifest_dir.join("whisher");
-    
+
     if source_dir.exists() {
         println!("cargo:warning=Copying whisher files to {:?}", output_dir);
-        
-        let cli_name = if cfg!(target_os = "windows") {
-            "whisper-cli.exe"
-        } else {
-            "whisper-cli"
-        };
-        
-        let cli_src = source_dir.join(cli_name);
-        let cli_dst = output_dir.join(cli_name);
+
+        let triple = target_triple();
+        let ext = if triple.contains("windows") { ".exe" } else { "" };
+        // Sidecars are checked in per target-triple (Tauri's external-binary
+        // convention) so cross-compiled builds don't clobber each other.
+        let cli_src_name = format!("whisper-cli-{}{}", triple, ext);
+        let cli_dst_name = format!("whisper-cli{}", ext);
+
+        let cli_src = source_dir.join(&cli_src_name);
+        let cli_dst = output_dir.join(&cli_dst_name);
         if cli_src.exists() {
-            fs::copy(&cli_src, &cli_dst).expect("Failed to copy whisper-cli");
-            println!("cargo:warning=Copied {} to {:?}", cli_name, cli_dst);
+            fs::copy(&cli_src, &cli_dst).expect("Failed to copy whisper-cli sidecar");
+            println!("cargo:warning=Copied {} to {:?}", cli_src_name, cli_dst);
+        } else {
+            println!(
+                "cargo:warning=No sidecar found for target {} (expected {:?})",
+                triple,
+                source_dir.join(&cli_src_name)
+            );
         }
-        
+
         let models_dir = source_dir.join("models");
         if models_dir.exists() {
             let output_models = output_dir.join("models");
             fs::create_dir_all(&output_models).expect("Failed to create models dir");
-            
+
             for entry in fs::read_dir(&models_dir).expect("Failed to read models dir") {
                 let entry = entry.expect("Failed to read entry");
                 if entry.file_type().expect("Failed to get file type").is_file() {
@@ -40,26 +58,7 @@ fn main() {
                 }
             }
         }
-        
-        println!("cargo:rerun-if-changed={}", source_dir.display());
-    }
-}
 
-fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) -> std::io::Result<()> {
-    fs::create_dir_all(dst)?;
-    
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        let src_path = entry.path();
-        let dst_path = dst.join(entry.file_name());
-        
-        if ty.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
-        } else {
-            fs::copy(&src_path, &dst_path)?;
-        }
+        println!("cargo:rerun-if-changed={}", source_dir.display());
     }
-    
-    Ok(())
 }
@@ -0,0 +1,208 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{Emitter, Manager};
+
+/// Static entry in the model registry: id, display name and the
+/// uncompressed size Hugging Face reports for the file. We deliberately do
+/// *not* hand-roll a SHA-256 here: `download_model` fetches the expected
+/// checksum straight from Hugging Face's own `X-Linked-ETag` (the git-LFS
+/// sha256 of the file) right before downloading, so there's no copied-in
+/// hex constant to drift from the real, current file.
+type ModelSpec = (&'static str, &'static str, u64);
+
+/// Catalog of ggml whisper.cpp models we know how to fetch, keyed by the
+/// id whisper.cpp itself uses (the `ggml-<id>.bin` file on disk).
+const MODEL_REGISTRY: &[ModelSpec] = &[
+    ("tiny", "Tiny", 77_691_713),
+    ("tiny.en", "Tiny (English)", 77_704_715),
+    ("base", "Base", 147_964_211),
+    ("base.en", "Base (English)", 147_951_465),
+    ("small", "Small", 487_601_967),
+    ("small.en", "Small (English)", 487_614_201),
+    ("medium", "Medium", 1_533_763_059),
+    ("medium.en", "Medium (English)", 1_533_774_781),
+    ("large-v3", "Large v3", 3_095_033_483),
+    ("small.en-q5_1", "Small (English, quantized)", 190_937_497),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub id: String,
+    pub display_name: String,
+    pub url: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelStatus {
+    pub id: String,
+    pub display_name: String,
+    pub installed: bool,
+    pub path: Option<String>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ModelDownloadProgress {
+    model_id: String,
+    downloaded_bytes: u64,
+    total_bytes: u64,
+}
+
+fn model_url(id: &str) -> String {
+    format!("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-{}.bin", id)
+}
+
+fn model_info(id: &str) -> Result<ModelInfo, String> {
+    MODEL_REGISTRY
+        .iter()
+        .find(|(model_id, ..)| *model_id == id)
+        .map(|(model_id, display_name, size_bytes)| ModelInfo {
+            id: model_id.to_string(),
+            display_name: display_name.to_string(),
+            url: model_url(model_id),
+            size_bytes: *size_bytes,
+        })
+        .ok_or_else(|| format!("Неизвестная модель: {}", id))
+}
+
+/// Resolves a Hugging Face `resolve/...` URL to the expected SHA-256 and
+/// the real CDN download URL, instead of trusting a hash baked into the
+/// binary. For git-LFS files (which all ggml model files are), HF's own
+/// `resolve` endpoint 302s to a cloudfront/S3 URL — the `X-Linked-ETag`
+/// header carrying the LFS object's sha256 is only present on that initial
+/// HF response, not on the CDN response a redirect-following client ends up
+/// looking at, so we HEAD with redirects disabled and follow `Location`
+/// ourselves (the same thing `huggingface_hub`'s `get_hf_file_metadata` does).
+async fn resolve_download(url: &str) -> Result<(String, String), String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("Не удалось создать HTTP-клиент: {}", e))?;
+
+    let response = client.head(url).send().await
+        .map_err(|e| format!("Не удалось получить метаданные модели: {}", e))?;
+
+    if !response.status().is_redirection() {
+        return Err(format!(
+            "Не удалось получить метаданные модели: сервер не перенаправил на CDN (статус {})",
+            response.status()
+        ));
+    }
+
+    let etag = response.headers().get("x-linked-etag")
+        .or_else(|| response.headers().get("etag"))
+        .ok_or_else(|| "Сервер не вернул контрольную сумму модели (ETag)".to_string())?
+        .to_str()
+        .map_err(|e| format!("Некорректный заголовок ETag: {}", e))?
+        .trim_matches('"');
+    let sha256 = etag.strip_prefix("sha256:").unwrap_or(etag).to_lowercase();
+
+    let download_url = response.headers().get("location")
+        .ok_or_else(|| "Сервер не вернул адрес перенаправления для скачивания".to_string())?
+        .to_str()
+        .map_err(|e| format!("Некорректный заголовок Location: {}", e))?
+        .to_string();
+
+    Ok((sha256, download_url))
+}
+
+fn all_models() -> Vec<ModelInfo> {
+    MODEL_REGISTRY
+        .iter()
+        .map(|(id, ..)| model_info(id).expect("static registry entry"))
+        .collect()
+}
+
+pub(crate) fn get_model_path(app_handle: &tauri::AppHandle, model_id: &str) -> Result<PathBuf, String> {
+    let info = model_info(model_id)?;
+    let app_data = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Не удалось получить директорию данных приложения: {}", e))?;
+
+    Ok(app_data.join("models").join(format!("ggml-{}.bin", info.id)))
+}
+
+#[tauri::command]
+pub async fn check_model_installed(app_handle: tauri::AppHandle) -> Result<Vec<ModelStatus>, String> {
+    all_models()
+        .into_iter()
+        .map(|info| {
+            let model_path = get_model_path(&app_handle, &info.id)?;
+            let installed = model_path.exists();
+            Ok(ModelStatus {
+                id: info.id,
+                display_name: info.display_name,
+                installed,
+                path: if installed {
+                    Some(model_path.to_string_lossy().to_string())
+                } else {
+                    None
+                },
+                size_bytes: info.size_bytes,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn download_model(model_id: String, app_handle: tauri::AppHandle) -> Result<String, String> {
+    let info = model_info(&model_id)?;
+    let model_path = get_model_path(&app_handle, &model_id)?;
+    let models_dir = model_path.parent()
+        .ok_or("Не удалось получить родительскую директорию")?;
+
+    // Создаём директорию если её нет
+    fs::create_dir_all(models_dir)
+        .map_err(|e| format!("Не удалось создать директорию для моделей: {}", e))?;
+
+    let (expected_sha256, download_url) = resolve_download(&info.url).await?;
+
+    // Скачиваем модель потоково, чтобы не держать её целиком в памяти
+    let client = reqwest::Client::new();
+    let response = client.get(&download_url).send()
+        .await
+        .map_err(|e| format!("Не удалось скачать модель: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Ошибка при скачивании модели: {}", response.status()));
+    }
+
+    let total_bytes = response.content_length().unwrap_or(info.size_bytes);
+    let mut downloaded_bytes: u64 = 0;
+    let mut hasher = Sha256::new();
+    let mut file = fs::File::create(&model_path)
+        .map_err(|e| format!("Не удалось создать файл модели: {}", e))?;
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Ошибка при загрузке модели: {}", e))?;
+
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .map_err(|e| format!("Не удалось записать данные модели: {}", e))?;
+        downloaded_bytes += chunk.len() as u64;
+
+        let _ = app_handle.emit("model-download-progress", ModelDownloadProgress {
+            model_id: model_id.clone(),
+            downloaded_bytes,
+            total_bytes,
+        });
+    }
+    drop(file);
+
+    let digest = format!("{:x}", hasher.finalize());
+    if digest != expected_sha256 {
+        let _ = fs::remove_file(&model_path);
+        return Err(format!(
+            "Контрольная сумма модели не совпадает (ожидалось {}, получено {}). Файл удалён, попробуйте скачать снова.",
+            expected_sha256, digest
+        ));
+    }
+
+    Ok(model_path.to_string_lossy().to_string())
+}
@@ -0,0 +1,524 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+use crate::models::get_model_path;
+use crate::whisper_cli::resolve_whisper_cli_path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranscriptionResult {
+    pub text: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// Timestamped segments parsed from whisper-cli's `-oj` output.
+    pub segments: Vec<Segment>,
+    /// Raw SRT/VTT text when `output_format` requested one, ready to hand
+    /// straight to `export_subtitles`.
+    pub subtitle_text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// Subtitle format to additionally request from whisper-cli, on top of the
+/// `-oj` JSON we always parse for `Segment`s.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Srt,
+    Vtt,
+}
+
+/// Options forwarded to whisper-cli for a single `transcribe_audio` call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptionOptions {
+    /// id from the model catalog (see `models::check_model_installed`),
+    /// e.g. `"tiny"` or `"large-v3"`.
+    pub model_id: String,
+    /// ISO-639-1 code, or `"auto"` for whisper's built-in language detection.
+    pub language: String,
+    /// Defaults to the available parallelism when unset.
+    pub threads: Option<u32>,
+    /// Maps to `-tr`: translate the result to English.
+    pub translate: bool,
+    /// Maps to `--prompt`: biases decoding towards this text (names, jargon, ...).
+    pub initial_prompt: Option<String>,
+    pub temperature: f32,
+    pub best_of: u32,
+    /// Extra subtitle format to have whisper-cli render alongside the text.
+    pub output_format: OutputFormat,
+}
+
+impl Default for TranscriptionOptions {
+    fn default() -> Self {
+        Self {
+            model_id: "tiny".to_string(),
+            language: "auto".to_string(),
+            threads: None,
+            translate: false,
+            initial_prompt: None,
+            temperature: 0.0,
+            best_of: 5,
+            output_format: OutputFormat::Text,
+        }
+    }
+}
+
+fn whisper_cli_args(options: &TranscriptionOptions) -> Vec<String> {
+    let threads = options.threads.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get() as u32)
+            .unwrap_or(4)
+    });
+
+    let mut args = vec![
+        "-l".to_string(),
+        options.language.clone(),
+        "-t".to_string(),
+        threads.to_string(),
+        "-tp".to_string(),
+        options.temperature.to_string(),
+        "-bo".to_string(),
+        options.best_of.to_string(),
+    ];
+
+    if options.translate {
+        args.push("-tr".to_string());
+    }
+
+    if let Some(prompt) = &options.initial_prompt {
+        args.push("--prompt".to_string());
+        args.push(prompt.clone());
+    }
+
+    // Always requested so we can parse timestamped Segments out of it.
+    args.push("-oj".to_string());
+    match options.output_format {
+        OutputFormat::Text => {}
+        OutputFormat::Srt => args.push("-osrt".to_string()),
+        OutputFormat::Vtt => args.push("-ovtt".to_string()),
+    }
+
+    args
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperJsonOutput {
+    transcription: Vec<WhisperJsonSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperJsonSegment {
+    offsets: WhisperJsonOffsets,
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperJsonOffsets {
+    from: u64,
+    to: u64,
+}
+
+/// Parses whisper-cli's `-oj` JSON schema into our `Segment`s.
+fn parse_segments_json(content: &str) -> Result<Vec<Segment>, serde_json::Error> {
+    let parsed: WhisperJsonOutput = serde_json::from_str(content)?;
+    Ok(parsed
+        .transcription
+        .into_iter()
+        .map(|seg| Segment {
+            start_ms: seg.offsets.from,
+            end_ms: seg.offsets.to,
+            text: seg.text.trim().to_string(),
+        })
+        .collect())
+}
+
+/// Reads and deletes `<output_file_base>.json`, returning the parsed
+/// segments. Absence or a parse failure is logged and treated as "no
+/// segments" rather than failing the whole transcription.
+fn take_segments(output_file_base: &Path) -> Vec<Segment> {
+    let json_path = PathBuf::from(format!("{}.json", output_file_base.to_string_lossy()));
+    let content = match std::fs::read_to_string(&json_path) {
+        Ok(content) => content,
+        Err(e) => {
+            log::warn!("whisper-cli не создал JSON с таймкодами {:?}: {}", json_path, e);
+            return Vec::new();
+        }
+    };
+    let _ = std::fs::remove_file(&json_path);
+
+    parse_segments_json(&content).unwrap_or_else(|e| {
+        log::warn!("не удалось разобрать JSON с таймкодами {:?}: {}", json_path, e);
+        Vec::new()
+    })
+}
+
+/// Reads and deletes the subtitle file whisper-cli wrote for `output_format`
+/// (`-osrt`/`-ovtt`), if any was requested.
+fn take_subtitle_text(output_file_base: &Path, output_format: OutputFormat) -> Option<String> {
+    let ext = match output_format {
+        OutputFormat::Text => return None,
+        OutputFormat::Srt => "srt",
+        OutputFormat::Vtt => "vtt",
+    };
+
+    let path = PathBuf::from(format!("{}.{}", output_file_base.to_string_lossy(), ext));
+    match std::fs::read_to_string(&path) {
+        Ok(content) => {
+            let _ = std::fs::remove_file(&path);
+            Some(content)
+        }
+        Err(e) => {
+            log::warn!("whisper-cli не создал файл субтитров {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Writes subtitle (or any text) content to a user-chosen path, e.g. the
+/// `subtitle_text` returned alongside a `TranscriptionResult`.
+#[tauri::command]
+pub fn export_subtitles(content: String, destination_path: String) -> Result<(), String> {
+    std::fs::write(&destination_path, content)
+        .map_err(|e| format!("Не удалось сохранить файл субтитров: {}", e))
+}
+
+/// Emitted on `transcription-progress` as whisper-cli reports `-pp` progress.
+#[derive(Debug, Clone, Serialize)]
+struct TranscriptionProgress {
+    percent: f32,
+}
+
+/// Emitted on `transcription-segment` for each recognized line, as it appears.
+#[derive(Debug, Clone, Serialize)]
+struct TranscriptionSegment {
+    text: String,
+}
+
+fn is_diagnostic_line(line: &str) -> bool {
+    line.starts_with("whisper_")
+        || line.starts_with("system_info")
+        || line.starts_with("main:")
+        || line.contains("processing")
+        || line.contains("load time")
+        || line.contains("mel time")
+        || line.contains("sample time")
+        || line.contains("encode time")
+        || line.contains("decode time")
+        || line.contains("batchd time")
+        || line.contains("prompt time")
+        || line.contains("total time")
+        || line.contains("fallbacks")
+        || line.is_empty()
+        || line == "[BLANK_AUDIO]"
+}
+
+/// Parses a `whisper_print_progress_callback: progress = NN%` style line.
+fn parse_progress_percent(line: &str) -> Option<f32> {
+    let rest = &line[line.find("progress = ")? + "progress = ".len()..];
+    rest.trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+#[tauri::command]
+pub async fn transcribe_audio(
+    file_path: String,
+    options: Option<TranscriptionOptions>,
+    app_handle: tauri::AppHandle,
+) -> Result<TranscriptionResult, String> {
+    let options = options.unwrap_or_default();
+    let path = PathBuf::from(&file_path);
+    if !path.exists() {
+        return Ok(TranscriptionResult {
+            text: String::new(),
+            success: false,
+            error: Some("Файл не найден".to_string()),
+            segments: Vec::new(),
+            subtitle_text: None,
+        });
+    }
+
+    // Проверяем наличие модели
+    let model_path = match get_model_path(&app_handle, &options.model_id) {
+        Ok(path) => path,
+        Err(e) => {
+            return Ok(TranscriptionResult {
+                text: String::new(),
+                success: false,
+                error: Some(e),
+                segments: Vec::new(),
+                subtitle_text: None,
+            });
+        }
+    };
+    if !model_path.exists() {
+        return Ok(TranscriptionResult {
+            text: String::new(),
+            success: false,
+            error: Some("Модель не установлена. Пожалуйста, установите модель.".to_string()),
+            segments: Vec::new(),
+            subtitle_text: None,
+        });
+    }
+
+    let whisper_cli_path = resolve_whisper_cli_path(&app_handle)?;
+    if !whisper_cli_path.exists() {
+        return Ok(TranscriptionResult {
+            text: String::new(),
+            success: false,
+            error: Some(format!(
+                "Whisper CLI не найден по пути: {:?}. Ожидалось имя файла «{}»",
+                whisper_cli_path,
+                whisper_cli_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            )),
+            segments: Vec::new(),
+            subtitle_text: None,
+        });
+    }
+
+    let output_dir = std::env::temp_dir();
+    let output_file_base = output_dir.join(format!("whisper_output_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()));
+
+    let mut child = Command::new(&whisper_cli_path)
+        .arg("-f")
+        .arg(&file_path)
+        .arg("-m")
+        .arg(&model_path)
+        .args(whisper_cli_args(&options))
+        .arg("-otxt")
+        .arg("-of")
+        .arg(&output_file_base)
+        .arg("-pp")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Не удалось запустить Whisper CLI: {}", e))?;
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let stderr = child.stderr.take().expect("stderr was piped");
+
+    let stdout_lines = Arc::new(Mutex::new(Vec::new()));
+    let stderr_lines = Arc::new(Mutex::new(Vec::new()));
+
+    let stdout_task = {
+        let app_handle = app_handle.clone();
+        let stdout_lines = stdout_lines.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let line = line.trim().to_string();
+                if let Some(percent) = parse_progress_percent(&line) {
+                    let _ = app_handle.emit("transcription-progress", TranscriptionProgress { percent });
+                    continue;
+                }
+                if is_diagnostic_line(&line) {
+                    continue;
+                }
+                let _ = app_handle.emit("transcription-segment", TranscriptionSegment { text: line.clone() });
+                stdout_lines.lock().await.push(line);
+            }
+        })
+    };
+
+    let stderr_task = {
+        let app_handle = app_handle.clone();
+        let stderr_lines = stderr_lines.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                // whisper-cli печатает прогресс `-pp` в stderr
+                if let Some(percent) = parse_progress_percent(line.trim()) {
+                    let _ = app_handle.emit("transcription-progress", TranscriptionProgress { percent });
+                }
+                log::debug!("whisper-cli: {}", line);
+                stderr_lines.lock().await.push(line);
+            }
+        })
+    };
+
+    let status = child.wait().await
+        .map_err(|e| format!("Не удалось дождаться завершения Whisper CLI: {}", e))?;
+
+    let _ = stdout_task.await;
+    let _ = stderr_task.await;
+
+    let stderr_text = stderr_lines.lock().await.join("\n");
+
+    let output_txt_file = format!("{}.txt", output_file_base.to_string_lossy());
+    let text = match std::fs::read_to_string(&output_txt_file) {
+        Ok(content) => {
+            let _ = std::fs::remove_file(&output_txt_file);
+            content.trim().to_string()
+        }
+        Err(e) => {
+            let text_from_stdout = stdout_lines.lock().await.join("\n");
+
+            if text_from_stdout.is_empty() {
+                // Читаем и чистим за собой любые .json/.srt/.vtt, которые
+                // whisper-cli всё же успел создать до этого сбоя.
+                let _ = take_segments(&output_file_base);
+                let _ = take_subtitle_text(&output_file_base, options.output_format);
+
+                log::error!(
+                    "whisper-cli не создал файл результата ({}) и не вывел распознанные строки в stdout; stderr: {}",
+                    e, stderr_text
+                );
+                return Ok(TranscriptionResult {
+                    text: String::new(),
+                    success: false,
+                    error: Some(format!("Не удалось прочитать результат из файла: {}. STDERR: {}", e, stderr_text)),
+                    segments: Vec::new(),
+                    subtitle_text: None,
+                });
+            }
+
+            text_from_stdout
+        }
+    };
+
+    let segments = take_segments(&output_file_base);
+    let subtitle_text = take_subtitle_text(&output_file_base, options.output_format);
+
+    if status.success() || !text.is_empty() {
+        Ok(TranscriptionResult {
+            text,
+            success: true,
+            error: None,
+            segments,
+            subtitle_text,
+        })
+    } else {
+        log::error!("whisper-cli завершился с ошибкой {:?}; stderr: {}", status, stderr_text);
+        Ok(TranscriptionResult {
+            text: String::new(),
+            success: false,
+            error: Some(format!("Whisper завершился с ошибкой. STDERR: {}", stderr_text)),
+            segments: Vec::new(),
+            subtitle_text: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_progress_percent_from_whisper_line() {
+        assert_eq!(
+            parse_progress_percent("whisper_print_progress_callback: progress = 42%"),
+            Some(42.0)
+        );
+        assert_eq!(
+            parse_progress_percent("whisper_print_progress_callback: progress = 100%"),
+            Some(100.0)
+        );
+    }
+
+    #[test]
+    fn parses_progress_percent_returns_none_for_unrelated_lines() {
+        assert_eq!(parse_progress_percent("main: processing audio"), None);
+        assert_eq!(parse_progress_percent(""), None);
+    }
+
+    #[test]
+    fn filters_out_whisper_diagnostic_lines() {
+        assert!(is_diagnostic_line(""));
+        assert!(is_diagnostic_line("whisper_init_from_file_no_state: loading model"));
+        assert!(is_diagnostic_line("system_info: n_threads = 4"));
+        assert!(is_diagnostic_line("main: processing 'file.wav'"));
+        assert!(is_diagnostic_line("[BLANK_AUDIO]"));
+    }
+
+    #[test]
+    fn keeps_recognized_speech_lines() {
+        assert!(!is_diagnostic_line("Привет, как дела?"));
+        assert!(!is_diagnostic_line("Hello, world!"));
+    }
+
+    #[test]
+    fn builds_default_whisper_cli_args() {
+        let args = whisper_cli_args(&TranscriptionOptions::default());
+        assert_eq!(args[0], "-l");
+        assert_eq!(args[1], "auto");
+        assert!(args.contains(&"-oj".to_string()));
+        assert!(!args.contains(&"-tr".to_string()));
+        assert!(!args.contains(&"--prompt".to_string()));
+        assert!(!args.contains(&"-osrt".to_string()));
+        assert!(!args.contains(&"-ovtt".to_string()));
+    }
+
+    #[test]
+    fn builds_whisper_cli_args_with_translate_prompt_and_subtitles() {
+        let options = TranscriptionOptions {
+            translate: true,
+            initial_prompt: Some("Имена: Иван, Пётр".to_string()),
+            output_format: OutputFormat::Srt,
+            ..TranscriptionOptions::default()
+        };
+        let args = whisper_cli_args(&options);
+        assert!(args.contains(&"-tr".to_string()));
+        assert!(args.contains(&"--prompt".to_string()));
+        assert!(args.contains(&"Имена: Иван, Пётр".to_string()));
+        assert!(args.contains(&"-osrt".to_string()));
+        assert!(!args.contains(&"-ovtt".to_string()));
+    }
+
+    #[test]
+    fn parses_segments_from_whisper_json_output() {
+        let json = r#"{
+            "transcription": [
+                {"offsets": {"from": 0, "to": 1500}, "text": " Привет, мир."},
+                {"offsets": {"from": 1500, "to": 3200}, "text": " Как дела?"}
+            ]
+        }"#;
+        let segments = parse_segments_json(json).expect("valid whisper json");
+        assert_eq!(
+            segments,
+            vec![
+                Segment { start_ms: 0, end_ms: 1500, text: "Привет, мир.".to_string() },
+                Segment { start_ms: 1500, end_ms: 3200, text: "Как дела?".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_segments_trims_surrounding_whitespace() {
+        let json = r#"{"transcription": [{"offsets": {"from": 0, "to": 100}, "text": "   padded   "}]}"#;
+        let segments = parse_segments_json(json).expect("valid whisper json");
+        assert_eq!(segments[0].text, "padded");
+    }
+
+    #[test]
+    fn parses_segments_returns_empty_vec_for_empty_transcription() {
+        let json = r#"{"transcription": []}"#;
+        assert_eq!(parse_segments_json(json).expect("valid whisper json"), Vec::new());
+    }
+
+    #[test]
+    fn parses_segments_errors_on_malformed_json() {
+        assert!(parse_segments_json("not json at all").is_err());
+        assert!(parse_segments_json(r#"{"wrong_key": []}"#).is_err());
+    }
+}
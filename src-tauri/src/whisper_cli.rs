@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use tauri::Manager;
+
+/// Rust target triple for the sidecar the current binary was built for.
+///
+/// Built only from compile-time `std::env::consts`, so it covers the
+/// triples Tauri actually ships for desktop (the `vendor`/`abi` components
+/// it assumes per OS); add a branch here before supporting a new target.
+fn target_triple() -> Result<&'static str, String> {
+    match (std::env::consts::ARCH, std::env::consts::OS) {
+        ("x86_64", "windows") => Ok("x86_64-pc-windows-msvc"),
+        ("aarch64", "windows") => Ok("aarch64-pc-windows-msvc"),
+        ("x86_64", "macos") => Ok("x86_64-apple-darwin"),
+        ("aarch64", "macos") => Ok("aarch64-apple-darwin"),
+        ("x86_64", "linux") => Ok("x86_64-unknown-linux-gnu"),
+        ("aarch64", "linux") => Ok("aarch64-unknown-linux-gnu"),
+        (arch, os) => Err(format!("whisper-cli sidecar: неподдерживаемая платформа {arch}-{os}")),
+    }
+}
+
+/// Name of the bundled sidecar as it comes out of the `whisher` source
+/// directory, suffixed with its target triple per build.rs.
+fn sidecar_name() -> Result<String, String> {
+    let ext = if cfg!(target_os = "windows") { ".exe" } else { "" };
+    Ok(format!("whisper-cli-{}{}", target_triple()?, ext))
+}
+
+/// Stable, triple-less name the sidecar is installed under once build.rs
+/// (dev) or the Tauri bundler (release resources) has placed it.
+fn runtime_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "whisper-cli.exe"
+    } else {
+        "whisper-cli"
+    }
+}
+
+/// Resolves the path whisper-cli should live at for the current build,
+/// without checking whether the file actually exists there yet — callers
+/// decide how to report a missing sidecar.
+pub(crate) fn resolve_whisper_cli_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    if cfg!(debug_assertions) {
+        // В dev режиме берём напрямую из whisher директории проекта, где
+        // бинарники лежат под именем с суффиксом триплета
+        Ok(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("whisher").join(sidecar_name()?))
+    } else {
+        // В релизе берём бинарник из whisher в директории ресурсов под
+        // стабильным именем без суффикса триплета (build.rs кладёт свою
+        // копию под этим именем в target/<profile>, а не в whisher)
+        let resource_dir = app_handle.path().resource_dir()
+            .map_err(|e| format!("Не удалось найти директорию ресурсов: {}", e))?;
+        Ok(resource_dir.join("whisher").join(runtime_name()))
+    }
+}